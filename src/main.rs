@@ -1,63 +1,380 @@
-use chrono::{Duration, Local, NaiveDateTime};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, Utc};
+use clap::{Parser, Subcommand};
 use csv::{ReaderBuilder, Trim, WriterBuilder};
+use flate2::read::GzDecoder;
 use regex::Regex;
-use std::collections::HashSet;
-use std::convert::TryInto;
-use std::env;
-use std::fs::{self, DirEntry, File, OpenOptions};
-use std::io::{self, BufReader, BufWriter};
-use std::path::Path;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read};
+use std::path::{Path, PathBuf};
+use std::thread;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-/// Filters files in a specified directory that match a naming pattern and were modified
-/// within a specified number of days back from the current date.
+/// Command-line interface for the dashboard data table builder.
 ///
-/// This function looks for files starting with "fwddmp.log.tmp" and filters them based on their
-/// last modified time, keeping only those modified within the last `days_back` days.
+/// The tool always needs a directory of log files to scan; what varies is how the date window
+/// used to select files and records is derived, which is captured by the [`Mode`] subcommand.
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    /// The path to the directory containing the log files.
+    path_to_log_files: String,
+    /// Rotate the output file once it would cross this many bytes.
+    #[arg(long)]
+    max_size: Option<u64>,
+    /// Rotate the output file at each local-day boundary.
+    #[arg(long)]
+    rotate_daily: bool,
+    /// Print a per-file and aggregate report of records read, kept, and skipped after processing.
+    #[arg(long)]
+    summary: bool,
+    /// Output format: plain CSV written to `events.csv` (default), or tab-separated rows printed
+    /// to stdout for `psql ... | COPY ... FROM STDIN`.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Csv)]
+    format: OutputFormat,
+    /// When `--format copy`, also print a `COPY events (...) FROM STDIN` header line.
+    #[arg(long)]
+    copy_header: bool,
+    #[command(subcommand)]
+    mode: Mode,
+}
+
+/// Selects whether processed entries are written as plain CSV or as COPY-ready rows.
+#[derive(clap::ValueEnum, Clone, Copy, Default)]
+enum OutputFormat {
+    #[default]
+    Csv,
+    Copy,
+}
+
+/// Selects how the `(start, end)` date window for filtering files and records is derived.
+#[derive(Subcommand)]
+enum Mode {
+    /// Keep records modified/timestamped within `days_back` days of now, the original behavior.
+    Days {
+        /// The number of days back from now to consider.
+        days_back: i64,
+    },
+    /// Keep records timestamped within an explicit, deterministic RFC3339 range.
+    Range {
+        /// Start of the range, RFC3339 (e.g. "2026-07-01T00:00:00Z").
+        #[arg(long)]
+        start: String,
+        /// End of the range, RFC3339 (e.g. "2026-07-26T00:00:00Z").
+        #[arg(long)]
+        end: String,
+    },
+}
+
+/// An inclusive `[start, end]` window used to decide whether a file or record should be kept.
+///
+/// Both the rolling `days_back` mode and the explicit `range` mode are resolved into a single
+/// `DateWindow` up front, so [`filter_files`], [`process_csv_file`], and [`filter_csv_by_date`]
+/// only ever need to reason about one interval shape.
+#[derive(Clone, Copy)]
+struct DateWindow {
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+}
+
+impl DateWindow {
+    /// Builds the window `[now - days_back days, now]`.
+    fn from_days_back(days_back: i64) -> Self {
+        let now = Local::now().naive_local();
+        let start = now - Duration::days(days_back);
+        Self { start, end: now }
+    }
+
+    /// Builds the window from two RFC3339 timestamps, e.g. `YYYY-MM-DDTHH:MM:SSZ`.
+    ///
+    /// # Errors
+    /// Returns an error if either `start` or `end` is not a valid RFC3339 timestamp.
+    fn from_rfc3339(start: &str, end: &str) -> Result<Self, chrono::ParseError> {
+        let start = DateTime::parse_from_rfc3339(start)?.naive_utc();
+        let end = DateTime::parse_from_rfc3339(end)?.naive_utc();
+        Ok(Self { start, end })
+    }
+
+    /// Returns whether `date_time` falls within the inclusive window.
+    fn contains(&self, date_time: NaiveDateTime) -> bool {
+        date_time >= self.start && date_time <= self.end
+    }
+
+    /// Returns whether `date_time` is at or after the window's lower bound.
+    ///
+    /// Used for file/archive-member *modification* times rather than record timestamps: a
+    /// file's mtime is when it was last written, which is always at or after the timestamps of
+    /// the records inside it, so checking it against the window's upper bound as well would
+    /// wrongly exclude a file whose contents fall in the window but that was itself last
+    /// touched after `end` (e.g. any historical `range` query, since every log file on disk has
+    /// an mtime no older than today). Callers that need exact selection still filter by
+    /// [`Self::contains`] at the record level.
+    fn after_start(&self, date_time: NaiveDateTime) -> bool {
+        date_time >= self.start
+    }
+}
+
+/// A single candidate log input discovered by [`filter_files`].
+///
+/// Plain and gzip-compressed files are matched directly by name; tar and tar.gz archives are
+/// matched by extension, then opened once so every member can be checked against the same name
+/// prefix and modification-time window, with matching members extracted into `data` during that
+/// same pass (see [`archive_member_sources`]). [`open_log_source`] turns any variant into a
+/// single `impl Read` so [`process_csv_file`] does not need to know which kind it got.
+enum LogSource {
+    Plain(PathBuf),
+    Gzip(PathBuf),
+    TarMember {
+        archive_path: PathBuf,
+        member_name: String,
+        data: Vec<u8>,
+    },
+}
+
+impl LogSource {
+    /// A human-readable label for progress logging.
+    fn describe(&self) -> String {
+        match self {
+            LogSource::Plain(path) | LogSource::Gzip(path) => path.display().to_string(),
+            LogSource::TarMember {
+                archive_path,
+                member_name,
+                ..
+            } => format!("{}::{member_name}", archive_path.display()),
+        }
+    }
+}
+
+/// Opens a reader over the contents of `source`, decompressing as needed.
+///
+/// Plain files are read directly; `.gz` files are wrapped in a [`GzDecoder`]; a tar member's
+/// bytes were already extracted by [`archive_member_sources`] in its single pass over the
+/// archive, so this just wraps that buffer in a cursor.
+///
+/// # Errors
+/// Returns an `io::Error` if the underlying file cannot be opened.
+fn open_log_source(source: LogSource) -> io::Result<Box<dyn Read>> {
+    match source {
+        LogSource::Plain(path) => Ok(Box::new(BufReader::new(File::open(path)?))),
+        LogSource::Gzip(path) => Ok(Box::new(BufReader::new(GzDecoder::new(File::open(path)?)))),
+        LogSource::TarMember { data, .. } => Ok(Box::new(io::Cursor::new(data))),
+    }
+}
+
+/// Discovers members of a `.tar`/`.tar.gz` archive named like `fwddmp.log.tmp*` whose own
+/// modification time is not older than `window`'s start, extracting each matching member's bytes
+/// immediately.
+///
+/// Unlike plain and gzip files, an archive's own modification time is not what's checked here;
+/// each member's `mtime` header is checked individually, since a long-lived archive can be
+/// appended to (or rewritten) well after some of its members were originally produced. As in
+/// [`filter_files`], only the window's lower bound is applied to this mtime; the upper bound is
+/// left to the record-level check downstream.
+///
+/// Matching members are read to completion as part of this same walk over `tar::Archive::entries`
+/// rather than deferred to [`open_log_source`]: `Entries` is a forward-only streaming iterator
+/// that cannot seek to a member by name, so locating each member by re-opening and re-scanning
+/// the archive per member (and, for `.tar.gz`, re-decompressing it each time) would cost O(member
+/// count) archive scans instead of one.
+fn archive_member_sources(archive_path: &Path, gzip: bool, window: &DateWindow) -> Vec<LogSource> {
+    let Ok(file) = File::open(archive_path) else {
+        return Vec::new();
+    };
+    let reader: Box<dyn Read> = if gzip {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    let mut archive = tar::Archive::new(reader);
+    let Ok(entries) = archive.entries() else {
+        return Vec::new();
+    };
+
+    let mut members = Vec::new();
+    for mut entry in entries.filter_map(Result::ok) {
+        let Ok(entry_path) = entry.path() else {
+            continue;
+        };
+        let file_name = entry_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if !file_name.starts_with("fwddmp.log.tmp") {
+            continue;
+        }
+
+        let Ok(mtime) = entry.header().mtime() else {
+            continue;
+        };
+        let Some(modified) = DateTime::<Utc>::from_timestamp(mtime as i64, 0) else {
+            continue;
+        };
+        if !window.after_start(modified.naive_utc()) {
+            continue;
+        }
+
+        let member_name = entry_path.to_string_lossy().into_owned();
+        let mut data = Vec::new();
+        if entry.read_to_end(&mut data).is_err() {
+            continue;
+        }
+
+        members.push(LogSource::TarMember {
+            archive_path: archive_path.to_path_buf(),
+            member_name,
+            data,
+        });
+    }
+
+    members
+}
+
+/// Path to the incremental-processing cache written by a successful run, relative to the
+/// current working directory.
+const CACHE_PATH: &str = ".dashboard_cache";
+
+/// A file fingerprint recorded in the mtime cache: the last-modified time (Unix seconds) and
+/// size observed the last time this tool considered the file.
+#[derive(Clone, Copy, PartialEq)]
+struct CacheEntry {
+    mtime_secs: i64,
+    size: u64,
+}
+
+impl CacheEntry {
+    /// Builds a fingerprint from a directory entry's metadata.
+    fn from_metadata(metadata: &fs::Metadata) -> Self {
+        let mtime_secs = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or(0);
+        Self {
+            mtime_secs,
+            size: metadata.len(),
+        }
+    }
+}
+
+/// Loads the mtime/size cache written by a prior successful run, if any.
+///
+/// The cache is a simple tab-separated `path\tmtime_secs\tsize` file. A missing or unreadable
+/// cache is treated as empty, so the first run always processes every candidate file.
+fn load_cache(cache_path: &Path) -> HashMap<PathBuf, CacheEntry> {
+    let Ok(contents) = fs::read_to_string(cache_path) else {
+        return HashMap::new();
+    };
+
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let path = PathBuf::from(fields.next()?);
+            let mtime_secs: i64 = fields.next()?.parse().ok()?;
+            let size: u64 = fields.next()?.parse().ok()?;
+            Some((path, CacheEntry { mtime_secs, size }))
+        })
+        .collect()
+}
+
+/// Persists `cache` to `cache_path`, overwriting whatever a prior run left there.
+///
+/// # Errors
+/// Returns an `io::Error` if `cache_path` cannot be written.
+fn save_cache(cache_path: &Path, cache: &HashMap<PathBuf, CacheEntry>) -> io::Result<()> {
+    let mut contents = String::new();
+    for (path, entry) in cache {
+        contents.push_str(&format!(
+            "{}\t{}\t{}\n",
+            path.display(),
+            entry.mtime_secs,
+            entry.size
+        ));
+    }
+    fs::write(cache_path, contents)
+}
+
+/// Discovers log inputs in a directory that match the `fwddmp.log.tmp` naming convention and
+/// whose modification time is not older than `window`'s start, skipping any file whose mtime and
+/// size are unchanged since the last successful run according to `cache`.
+///
+/// Three kinds of input are discovered:
+/// - Plain files named `fwddmp.log.tmp*`.
+/// - Gzip-compressed files named `fwddmp.log.tmp*.gz`.
+/// - Members of any `.tar`/`.tar.gz` archive in `path` whose own name matches the
+///   `fwddmp.log.tmp` prefix and whose own modification time is not older than `window`'s start
+///   (see [`archive_member_sources`]).
+///
+/// Only the window's lower bound is applied here (see [`DateWindow::after_start`]); a file's
+/// mtime reflects when it was last written, not the timestamps of the records inside it, so the
+/// upper bound is left to the exact, record-level check in [`process_csv_file`]/
+/// [`filter_csv_by_date`].
 ///
 /// # Arguments
 /// - `path`: A reference to the path of the directory to search in.
-/// - `days_back`: The number of days back from the current date to consider when filtering files.
-///                Files modified more recently than this will be included in the results.
+/// - `window`: A candidate's modification time must be at or after `window.start`.
+/// - `cache`: Fingerprints recorded by [`load_cache`] for files seen on a prior run.
 ///
 /// # Returns
-/// A vector of `DirEntry` representing the filtered files that match the criteria.
+/// A tuple of the [`LogSource`]s to process this run, and the fresh fingerprint of every
+/// candidate file seen in `path` (whether skipped or processed), for the caller to persist via
+/// [`save_cache`] once the run succeeds.
 ///
 /// # Panics
-/// Panics if reading the directory fails, if there is an error calculating time durations,
-/// or if converting system times to a comparable format fails.
-fn filter_files(path: &Path, days_back: i64) -> Vec<DirEntry> {
-    let now = Local::now();
-    fs::read_dir(path)
+/// Panics if reading the directory fails.
+fn filter_files(
+    path: &Path,
+    window: &DateWindow,
+    cache: &HashMap<PathBuf, CacheEntry>,
+) -> (Vec<LogSource>, HashMap<PathBuf, CacheEntry>) {
+    let mut sources = Vec::new();
+    let mut seen = HashMap::new();
+
+    for entry in fs::read_dir(path)
         .expect("Error reading directory")
         .filter_map(Result::ok)
-        .filter(|entry| {
-            entry
-                .file_name()
-                .to_string_lossy()
-                .starts_with("fwddmp.log.tmp")
-                && entry
-                    .metadata()
-                    .map(|meta| {
-                        let file_time = meta
-                            .modified()
-                            .unwrap_or_else(|_| SystemTime::now())
-                            .duration_since(UNIX_EPOCH)
-                            .expect("Error calculating time duration")
-                            .as_secs();
-
-                        // Safely convert chrono::DateTime to u64 for comparison
-                        let comparison_time = (now
-                            - Duration::try_days(days_back).expect("Valid duration"))
-                        .timestamp()
-                        .try_into()
-                        .expect("Timestamp conversion error");
-
-                        file_time > comparison_time
-                    })
-                    .unwrap_or(false)
-        })
-        .collect()
+    {
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let is_candidate = file_name.starts_with("fwddmp.log.tmp")
+            || file_name.ends_with(".tar.gz")
+            || file_name.ends_with(".tgz")
+            || file_name.ends_with(".tar");
+        if !is_candidate {
+            continue;
+        }
+
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let entry_path = entry.path();
+        let fingerprint = CacheEntry::from_metadata(&metadata);
+        seen.insert(entry_path.clone(), fingerprint);
+
+        if cache.get(&entry_path) == Some(&fingerprint) {
+            continue;
+        }
+
+        let modified = metadata.modified().unwrap_or_else(|_| SystemTime::now());
+        let file_time = DateTime::<Local>::from(modified).naive_local();
+
+        if file_name.starts_with("fwddmp.log.tmp") && file_name.ends_with(".gz") {
+            if window.after_start(file_time) {
+                sources.push(LogSource::Gzip(entry_path));
+            }
+        } else if file_name.starts_with("fwddmp.log.tmp") {
+            if window.after_start(file_time) {
+                sources.push(LogSource::Plain(entry_path));
+            }
+        } else if file_name.ends_with(".tar.gz") || file_name.ends_with(".tgz") {
+            sources.extend(archive_member_sources(&entry_path, true, window));
+        } else if file_name.ends_with(".tar") {
+            sources.extend(archive_member_sources(&entry_path, false, window));
+        }
+    }
+
+    (sources, seen)
 }
 
 /// Static global regex pattern used for cleaning event descriptions.
@@ -91,39 +408,106 @@ fn clean_event_description(event_description: &str) -> String {
 /// from the current date. Additionally, it checks that the event 'Blocked' status is set to '1',
 /// indicating the 'Source IP Address' has been blocked. For each qualifying record, it constructs a
 /// string that combines several fields: 'Date/Time', 'Source IP Address', 'Destination IP Address',
-/// a cleaned 'Event Description', and 'Priority'. Each unique combination is added to a `HashSet`
-/// to ensure no duplicates.
+/// a cleaned 'Event Description', and 'Priority'. Each unique combination is kept in a `HashSet`
+/// to drop duplicates, then the deduplicated entries are sorted ascending by their parsed
+/// 'Date/Time' so the caller can treat the result as a single pre-sorted stream.
 ///
+/// Per-file counters reported by [`process_csv_file`] and folded together in `main` when
+/// `--summary` is set, so operators can see why a given export has fewer rows than the raw logs.
+#[derive(Default, Clone)]
+struct FileStats {
+    read: u64,
+    passed_cutoff: u64,
+    blocked: u64,
+    duplicates: u64,
+    unparseable_dates: u64,
+    kept: u64,
+    min_date: Option<NaiveDateTime>,
+    max_date: Option<NaiveDateTime>,
+}
+
+impl FileStats {
+    /// Folds `other` into `self`, taking the overall min/max of the two `Date/Time` ranges.
+    fn merge(&mut self, other: &FileStats) {
+        self.read += other.read;
+        self.passed_cutoff += other.passed_cutoff;
+        self.blocked += other.blocked;
+        self.duplicates += other.duplicates;
+        self.unparseable_dates += other.unparseable_dates;
+        self.kept += other.kept;
+        self.min_date = match (self.min_date, other.min_date) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (min, None) => min,
+            (None, max) => max,
+        };
+        self.max_date = match (self.max_date, other.max_date) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (max, None) => max,
+            (None, max) => max,
+        };
+    }
+}
+
 /// # Arguments
-/// * `file_path` - A reference to the path of the CSV file to be processed.
-/// * `days_back` - The number of days back from the current date to filter records by their
-///   'Date/Time' field. Only records with a 'Date/Time' on or after this threshold are included.
+/// * `reader` - Any `impl Read` over the file's CSV contents, whether sourced from a plain file,
+///   a gzip-decompressed file, or an extracted tar member (see [`open_log_source`]).
+/// * `window` - The inclusive date range a record's 'Date/Time' field must fall within to be kept.
 ///
 /// # Returns
-/// A `Result` wrapping a `HashSet<String>` containing the unique, cleaned entries from the file.
-/// Each entry in the `HashSet` is a comma-separated string with the format:
+/// A `Result` wrapping the unique, cleaned entries from the file in ascending 'Date/Time' order
+/// alongside a [`FileStats`] summarizing what happened to every record read. Each entry is a
+/// comma-separated string with the format:
 /// "Date/Time,Source IP,Destination IP,Event Description,Priority"
 ///
 /// The 'Event Description' field is cleaned to remove specific patterns using a regular expression,
 /// which typically involves stripping metadata or formatting prefixes.
 ///
 /// # Errors
-/// Returns an `io::Error` if reading the file fails at any point, including issues with opening the file,
-/// reading its contents, or parsing individual records.
+/// Returns an `io::Error` if reading from `reader` fails at any point, including issues with
+/// parsing individual records.
 ///
 /// # Panics
-/// This function can panic if parsing the 'Date/Time' strings to `NaiveDateTime` fails for any line that
-/// is attempted to be included based on the `days_back` criteria. It can also panic if the regex used
-/// for cleaning 'Event Description' fields fails to compile or apply, although this is unlikely with a
-/// correctly specified regex pattern.
-fn process_csv_file(file_path: &Path, days_back: i64) -> io::Result<HashSet<String>> {
+/// This function can panic if the regex used for cleaning 'Event Description' fields fails to
+/// compile or apply, although this is unlikely with a correctly specified regex pattern.
+/// The five output fields of a single kept, cleaned record.
+///
+/// Carrying these as structured data (rather than a pre-joined comma string) through the merge
+/// and into [`write_to_csv`]/[`to_copy_row`] means a comma, tab, or other delimiter character
+/// inside `event_description` (free text from the source logs) can never misalign the other
+/// fields.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct LogEntry {
+    date_time: String,
+    source_ip: String,
+    destination_ip: String,
+    event_description: String,
+    priority: String,
+}
+
+impl LogEntry {
+    /// The five fields in column order, for handing to `csv::Writer::write_record` or a COPY
+    /// row formatter.
+    fn fields(&self) -> [&str; 5] {
+        [
+            &self.date_time,
+            &self.source_ip,
+            &self.destination_ip,
+            &self.event_description,
+            &self.priority,
+        ]
+    }
+}
+
+fn process_csv_file(
+    reader: impl Read,
+    window: &DateWindow,
+) -> io::Result<(Vec<(NaiveDateTime, LogEntry)>, FileStats)> {
     let mut rdr = ReaderBuilder::new()
         .has_headers(false)
         .trim(Trim::All)
-        .from_path(file_path)?;
-    let mut unique_entries = HashSet::new();
-    let now = Local::now();
-    let cutoff = now - Duration::days(days_back);
+        .from_reader(reader);
+    let mut unique_entries: HashSet<(NaiveDateTime, LogEntry)> = HashSet::new();
+    let mut stats = FileStats::default();
 
     for result in rdr.records() {
         let record = match result {
@@ -133,64 +517,244 @@ fn process_csv_file(file_path: &Path, days_back: i64) -> io::Result<HashSet<Stri
                 continue;
             }
         };
+        stats.read += 1;
+
         let date_time_str = record.get(4).unwrap_or_default();
-        if let Ok(date_time) = NaiveDateTime::parse_from_str(date_time_str, "%Y/%m/%d %H:%M:%S") {
-            if date_time > cutoff.naive_local() && record.get(11).unwrap_or_default() == "1" {
-                let source_ip = record.get(6).unwrap_or_default();
-                let destination_ip = record.get(12).unwrap_or_default();
-                let event_description = record.get(3).unwrap_or_default();
-                let cleaned_description = clean_event_description(event_description);
-                let priority = record.get(1).unwrap_or_default();
-
-                let entry = format!(
-                    "{date_time_str},{source_ip},{destination_ip},{cleaned_description},{priority}"
-                );
-                unique_entries.insert(entry);
-            }
-        } else {
+        let Ok(date_time) = NaiveDateTime::parse_from_str(date_time_str, "%Y/%m/%d %H:%M:%S")
+        else {
+            stats.unparseable_dates += 1;
             println!("Skipping record with invalid date: {date_time_str}");
             continue;
+        };
+
+        stats.min_date = Some(stats.min_date.map_or(date_time, |min| min.min(date_time)));
+        stats.max_date = Some(stats.max_date.map_or(date_time, |max| max.max(date_time)));
+
+        if !window.contains(date_time) {
+            continue;
+        }
+        stats.passed_cutoff += 1;
+
+        if record.get(11).unwrap_or_default() != "1" {
+            continue;
+        }
+        stats.blocked += 1;
+
+        let source_ip = record.get(6).unwrap_or_default();
+        let destination_ip = record.get(12).unwrap_or_default();
+        let event_description = record.get(3).unwrap_or_default();
+        let cleaned_description = clean_event_description(event_description);
+        let priority = record.get(1).unwrap_or_default();
+
+        let entry = LogEntry {
+            date_time: date_time_str.to_string(),
+            source_ip: source_ip.to_string(),
+            destination_ip: destination_ip.to_string(),
+            event_description: cleaned_description,
+            priority: priority.to_string(),
+        };
+        if !unique_entries.insert((date_time, entry)) {
+            stats.duplicates += 1;
         }
     }
 
-    Ok(unique_entries)
+    stats.kept = unique_entries.len() as u64;
+    let mut sorted_entries: Vec<_> = unique_entries.into_iter().collect();
+    sorted_entries.sort_by_key(|(date_time, _)| *date_time);
+
+    Ok((sorted_entries, stats))
 }
 
-/// Appends a set of string entries to a CSV file at the specified path.
+/// Performs a streaming k-way merge of several pre-sorted per-file entry streams.
 ///
-/// This function takes a `HashSet` of string entries, each expected to be a
-/// comma-separated value (CSV) string, and appends them to a CSV file. The order of the entries
-/// in the output file is not guaranteed due to the nature of `HashSet`. The function creates
-/// the file if it does not exist and appends to it if it does.
+/// Each element of `streams` is the ascending-by-'Date/Time' output of [`process_csv_file`] for
+/// one worker. A min-heap of `(Reverse(NaiveDateTime), worker_index)` is seeded with the head
+/// record of every stream; the smallest element is repeatedly popped, emitted, and replaced with
+/// the next record from the same worker's stream. Because each input stream is already sorted,
+/// the heap top is always the global minimum, so the merged output is globally time-ordered
+/// without ever buffering every record at once. Entries equal to any entry already emitted are
+/// dropped, via a `seen` set keyed on the full entry, so duplicates spanning different files are
+/// still deduplicated even when they aren't adjacent in pop order (e.g. two equal-timestamp
+/// duplicates from different workers, separated by an equal-timestamp record from a third
+/// worker).
 ///
 /// # Arguments
-/// * `entries` - A `HashSet<String>` containing the CSV-formatted entries to be appended to the file.
-///   Each string in the set should be a single CSV record.
-/// * `output_path` - A reference to the path where the output CSV file will be written. If a file
-///   at this path already exists, the entries will be appended to it. If it does not exist, a new file
-///   will be created.
+/// * `streams` - One ascending-sorted `(NaiveDateTime, LogEntry)` vector per worker, as returned
+///   by [`process_csv_file`].
 ///
 /// # Returns
-/// An `io::Result<()>` indicating the success of the operation. Returns `Ok(())` if the append
-/// operation completes successfully.
+/// A `Vec<LogEntry>` of the merged, deduplicated entries in ascending 'Date/Time' order.
+fn merge_sorted_streams(streams: Vec<Vec<(NaiveDateTime, LogEntry)>>) -> Vec<LogEntry> {
+    let mut cursors: Vec<std::vec::IntoIter<(NaiveDateTime, LogEntry)>> =
+        streams.into_iter().map(|stream| stream.into_iter()).collect();
+    let mut heap: BinaryHeap<Reverse<(NaiveDateTime, usize, LogEntry)>> = BinaryHeap::new();
+
+    for (worker_index, cursor) in cursors.iter_mut().enumerate() {
+        if let Some((date_time, entry)) = cursor.next() {
+            heap.push(Reverse((date_time, worker_index, entry)));
+        }
+    }
+
+    let mut merged = Vec::new();
+    let mut seen: HashSet<LogEntry> = HashSet::new();
+
+    while let Some(Reverse((date_time, worker_index, entry))) = heap.pop() {
+        if seen.insert(entry.clone()) {
+            merged.push(entry);
+        }
+
+        if let Some((next_date_time, next_entry)) = cursors[worker_index].next() {
+            heap.push(Reverse((next_date_time, worker_index, next_entry)));
+        }
+    }
+
+    merged
+}
+
+/// Estimates the on-disk size in bytes of a CSV record built from `fields`, matching how
+/// `csv::Writer` encodes it: values joined by `,`, quoted (with each interior `"` doubled) if a
+/// value contains a comma, quote, or newline, and the whole record terminated by `\n`.
 ///
-/// # Errors
-/// Returns an `io::Error` if the file cannot be created or appended to. This includes errors related
-/// to file permissions, disk space, or other I/O errors.
-fn write_to_csv(entries: HashSet<String>, output_path: &Path) -> io::Result<()> {
-    let file = OpenOptions::new()
-        .append(true)
-        .create(true)
-        .open(output_path)?;
+/// This is computed from the record's own field lengths rather than read back from the
+/// underlying writer, because `csv::Writer` buffers internally (it only flushes to the file in
+/// ~8KB chunks), so a byte count sourced from the file/stream lags well behind what has actually
+/// been queued to write.
+fn estimated_record_len<'a>(fields: impl IntoIterator<Item = &'a str>) -> u64 {
+    let mut total = 0u64;
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            total += 1; // delimiter
+        }
+        total += field.len() as u64;
+        if field.contains(',') || field.contains('"') || field.contains('\n') {
+            total += 2 + field.matches('"').count() as u64; // surrounding + doubled quotes
+        }
+    }
+    total + 1 // record terminator
+}
 
-    let mut wtr = WriterBuilder::new().from_writer(file);
+/// Appends entries to `base_path`, rotating to a fresh, timestamped file once the active file
+/// would cross `max_size` bytes and/or a local-day boundary, when those limits are configured.
+///
+/// The first rotation (if any) moves off of `base_path` onto a file named
+/// `events.YYYY-MM-DD-HHMMSS.csv`; later rotations during the same run repeat that naming. With
+/// both `max_size` and `rotate_daily` unset, this behaves exactly like the plain append it
+/// replaces.
+///
+/// Every path written to this run (`base_path` plus any rotated successors) is remembered in
+/// `written_paths`, so the caller can run retention/dedup over all of them, not just whichever
+/// one happened to be active when writing finished.
+struct RotatingCsvWriter {
+    max_size: Option<u64>,
+    rotate_daily: bool,
+    current_day: NaiveDate,
+    active_path: PathBuf,
+    written_paths: Vec<PathBuf>,
+    logical_bytes: u64,
+    writer: csv::Writer<File>,
+}
+
+impl RotatingCsvWriter {
+    /// Opens `base_path` for appending, seeding the byte count from its existing size so
+    /// `max_size` rotation accounts for content written by prior runs.
+    fn new(base_path: &Path, max_size: Option<u64>, rotate_daily: bool) -> io::Result<Self> {
+        let logical_bytes = fs::metadata(base_path).map(|m| m.len()).unwrap_or(0);
+        let file = OpenOptions::new().append(true).create(true).open(base_path)?;
+        let writer = WriterBuilder::new().from_writer(file);
+
+        Ok(Self {
+            max_size,
+            rotate_daily,
+            current_day: Local::now().date_naive(),
+            active_path: base_path.to_path_buf(),
+            written_paths: vec![base_path.to_path_buf()],
+            logical_bytes,
+            writer,
+        })
+    }
+
+    /// Opens a brand-new, timestamped file and makes it the active destination for writes.
+    fn rotate(&mut self, now: DateTime<Local>) -> io::Result<()> {
+        self.writer.flush()?;
+
+        let path = PathBuf::from(format!("events.{}.csv", now.format("%Y-%m-%d-%H%M%S")));
+        let file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&path)?;
+        self.writer = WriterBuilder::new().from_writer(file);
+        self.active_path = path.clone();
+        self.written_paths.push(path);
+        self.current_day = now.date_naive();
+        self.logical_bytes = 0;
+        Ok(())
+    }
+
+    /// Writes a single entry's fields as one CSV record, rotating beforehand if it would push
+    /// the active file over `max_size` or if a local-day boundary has passed since the last
+    /// rotation.
+    fn write_entry(&mut self, entry: &LogEntry) -> io::Result<()> {
+        let now = Local::now();
+
+        let record_len = estimated_record_len(entry.fields());
+        let would_exceed_size = self
+            .max_size
+            .map(|max| self.logical_bytes + record_len > max)
+            .unwrap_or(false);
+        let crossed_day_boundary = self.rotate_daily && now.date_naive() != self.current_day;
+
+        if would_exceed_size || crossed_day_boundary {
+            self.rotate(now)?;
+        }
+
+        self.writer.write_record(entry.fields())?;
+        self.logical_bytes += record_len;
+        Ok(())
+    }
+
+    /// Flushes the active file and returns every path written to this run, in the order they
+    /// were first opened.
+    fn finish(mut self) -> io::Result<Vec<PathBuf>> {
+        self.writer.flush()?;
+        Ok(self.written_paths)
+    }
+}
+
+/// Appends a sequence of entries to a CSV file, rotating to timestamped files as configured by
+/// `max_size` and `rotate_daily`.
+///
+/// This function takes an ordered list of [`LogEntry`] values and appends each as one CSV
+/// record, in order, via a [`RotatingCsvWriter`].
+///
+/// # Arguments
+/// * `entries` - A `Vec<LogEntry>` containing the entries to be appended, in the order they
+///   should be written.
+/// * `base_path` - The path the output CSV file starts at. If a file at this path already
+///   exists, entries are appended to it until (if ever) rotation moves writes to a new file.
+/// * `max_size` - Rotate once the active file would cross this many bytes, if set.
+/// * `rotate_daily` - Rotate at each local-day boundary, if true.
+///
+/// # Returns
+/// Every path entries ended up being written to this run (`base_path` plus any rotated
+/// successors), for the caller to run further processing (e.g. retention filtering) against —
+/// rotation must not let any of them skip that pass.
+///
+/// # Errors
+/// Returns an `io::Error` if a file cannot be created, appended to, or flushed. This includes
+/// errors related to file permissions, disk space, or other I/O errors.
+fn write_to_csv(
+    entries: Vec<LogEntry>,
+    base_path: &Path,
+    max_size: Option<u64>,
+    rotate_daily: bool,
+) -> io::Result<Vec<PathBuf>> {
+    let mut writer = RotatingCsvWriter::new(base_path, max_size, rotate_daily)?;
 
     for entry in entries {
-        wtr.write_record(entry.split(','))?;
+        writer.write_entry(&entry)?;
     }
 
-    wtr.flush()?;
-    Ok(())
+    writer.finish()
 }
 
 /// Filters and sorts entries in a CSV file based on a date threshold and removes duplicates.
@@ -202,8 +766,7 @@ fn write_to_csv(entries: HashSet<String>, output_path: &Path) -> io::Result<()>
 /// # Arguments
 /// * `input_path` - A reference to the path of the CSV file to be processed. This file should contain
 ///   records with a date field as the first value in each record.
-/// * `days_back` - The number of days back from the current date to use as a cutoff for filtering records.
-///   Records with a date older than this will be excluded from the output.
+/// * `window` - The inclusive date range a record's date field must fall within to be kept.
 ///
 /// # Returns
 /// An `io::Result<()>` that indicates the success or failure of the read, write, and file operations.
@@ -215,10 +778,7 @@ fn write_to_csv(entries: HashSet<String>, output_path: &Path) -> io::Result<()>
 ///
 /// # Panics
 /// This function panics if the date parsing fails, indicating invalid date formats in the input CSV.
-fn filter_csv_by_date(input_path: &Path, days_back: i64) -> io::Result<()> {
-    let now = Local::now().naive_local(); // Use naive_local to avoid timezone issues
-    let cutoff = now - Duration::days(days_back);
-
+fn filter_csv_by_date(input_path: &Path, window: &DateWindow) -> io::Result<()> {
     let file = File::open(input_path)?;
     let mut reader = ReaderBuilder::new()
         .trim(Trim::All)
@@ -230,7 +790,7 @@ fn filter_csv_by_date(input_path: &Path, days_back: i64) -> io::Result<()> {
         let record = result?;
         if let Some(date_str) = record.get(0) {
             if let Ok(date_time) = NaiveDateTime::parse_from_str(date_str, "%Y/%m/%d %H:%M:%S") {
-                if date_time > cutoff {
+                if window.contains(date_time) {
                     // Convert the record to a string for hashing and comparison
                     let record_str = record.iter().collect::<Vec<&str>>().join(",");
                     records_to_keep.insert(record_str);
@@ -268,33 +828,115 @@ fn filter_csv_by_date(input_path: &Path, days_back: i64) -> io::Result<()> {
     Ok(())
 }
 
+/// Prints a per-file and aggregate report of how many records were read, kept, and skipped.
+///
+/// Used when `--summary` is passed, this surfaces the counters `process_csv_file` accumulates
+/// per file so operators can see why a given export has fewer rows than the raw logs.
+fn print_summary(per_file: &[(String, FileStats)], aggregate: &FileStats) {
+    fn format_date(date: Option<NaiveDateTime>) -> String {
+        date.map(|d| d.to_string()).unwrap_or_else(|| "-".to_string())
+    }
+
+    println!("--- Summary ---");
+    for (label, stats) in per_file {
+        println!(
+            "{label}: read={}, passed_cutoff={}, blocked={}, duplicates={}, unparseable_dates={}, kept={}, min={}, max={}",
+            stats.read,
+            stats.passed_cutoff,
+            stats.blocked,
+            stats.duplicates,
+            stats.unparseable_dates,
+            stats.kept,
+            format_date(stats.min_date),
+            format_date(stats.max_date),
+        );
+    }
+    println!(
+        "TOTAL: read={}, passed_cutoff={}, blocked={}, duplicates={}, unparseable_dates={}, kept={}, min={}, max={}",
+        aggregate.read,
+        aggregate.passed_cutoff,
+        aggregate.blocked,
+        aggregate.duplicates,
+        aggregate.unparseable_dates,
+        aggregate.kept,
+        format_date(aggregate.min_date),
+        format_date(aggregate.max_date),
+    );
+}
+
+/// Escapes a single field for the Postgres COPY text format and normalizes an empty field to
+/// the `\N` NULL marker.
+///
+/// A literal backslash, tab, or newline in the field (e.g. from free-text `event_description`)
+/// would otherwise be read by `COPY` as an escape sequence, a column boundary, or a row
+/// boundary, respectively, so each is backslash-escaped before the field is emitted.
+fn escape_copy_field(field: &str) -> String {
+    if field.is_empty() {
+        return "\\N".to_string();
+    }
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// Converts a [`LogEntry`] into a tab-separated row suitable for `COPY ... FROM STDIN`.
+///
+/// Operating on the entry's already-separated fields (rather than splitting a comma-joined
+/// string) means a comma inside `event_description` can't be mistaken for a field boundary.
+fn to_copy_row(entry: &LogEntry) -> String {
+    entry
+        .fields()
+        .iter()
+        .map(|field| escape_copy_field(field))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+/// Prints `entries` as tab-separated COPY rows to stdout, optionally preceded by a matching
+/// `COPY events (...) FROM STDIN` header so the output can be piped straight into `psql`.
+fn print_copy_rows(entries: &[LogEntry], copy_header: bool) {
+    if copy_header {
+        println!(
+            "COPY events (date_time, source_ip, destination_ip, event_description, priority) FROM STDIN;"
+        );
+    }
+    for entry in entries {
+        println!("{}", to_copy_row(entry));
+    }
+}
+
 /// Entry point for the CSV data processing application.
 ///
-/// This application processes CSV files from a specified directory, filters the entries based on their
-/// modification date to consider only recent data, extracts and cleans data entries, and then writes the
-/// unique and sorted entries to a new CSV file named "events.csv". The cleaning process involves removing
-/// specified patterns from the 'Event Description' field using a regular expression. Additionally, the
-/// entries are initially sorted by the 'Date/Time' field in descending order and filtered to include only entries
-/// from the last specified number of days provided by the user. After writing the initial processed data, the
-/// entries are further filtered to a default retention period of 15 days before final writing to the output file.
+/// This application processes CSV files from a specified directory, filters the entries based on a
+/// date window, extracts and cleans data entries, and then writes the unique and sorted entries to
+/// "events.csv" (or a rotated successor, see below). The cleaning process involves removing
+/// specified patterns from the 'Event Description' field using a regular expression. The date
+/// window is either a rolling `days_back` cutoff or an explicit RFC3339 `--start`/`--end` range,
+/// selected via the `days` or `range` subcommand. After writing the initial processed data, the
+/// entries are further filtered to a retention window before final writing to the output file:
+/// the rolling default of 15 days in `days` mode, or the requested range itself in `range` mode,
+/// so a historical export isn't immediately pruned back out by a fixed recent cutoff. Files whose
+/// mtime and size are unchanged since the last successful run (see [`load_cache`]/[`save_cache`])
+/// are skipped, so repeated runs over a large, mostly-static log directory only reprocess what
+/// actually changed — except under `--format copy`, which always does a full, uncached scan and
+/// never updates the cache, since it never merges into `events.csv` the way the CSV path does.
 ///
 /// # Arguments
-/// The application accepts two command-line arguments:
-/// - `path_to_log_files`: The path to the directory containing the log files.
-/// - `days_back`: The number of days back to consider when initially filtering files based on their
-///   modification date. Only entries within this user-specified date range are initially considered.
+/// The application is parsed with `clap` into a [`Cli`]: a `path_to_log_files` positional argument,
+/// optional `--max-size`/`--rotate-daily` output rotation flags, an optional `--summary` flag (see
+/// [`print_summary`]), an optional `--format`/`--copy-header` pair selecting plain CSV or
+/// COPY-ready output (see [`print_copy_rows`]), and a `days`/`range` subcommand selecting the
+/// [`Mode`] used to build the [`DateWindow`].
 ///
 /// # Output
-/// The output is a CSV file named "events.csv", which will be created or overwritten
-/// in the current working directory. This file contains the processed entries without
-/// duplicates, initially sorted by the 'Date/Time' field in descending order and filtered
-/// by the days specified by the user, and finally by a default retention period of 7 days.
-///
-/// # Exit Codes
-/// The application will exit with one of the following codes:
-/// - `0`: The operation completed successfully.
-/// - `1`: The operation failed due to incorrect usage (e.g., not enough arguments
-///   were provided).
+/// The output is a CSV file named "events.csv" in the current working directory, which will be
+/// created or appended to. If `--max-size` or `--rotate-daily` is set and the active file would
+/// cross that bound, writing moves to a fresh `events.YYYY-MM-DD-HHMMSS.csv` file instead. This
+/// file contains the processed entries without duplicates, initially sorted by the 'Date/Time'
+/// field in descending order and filtered to the requested date window, and finally by a default
+/// retention period of 15 days.
 ///
 /// # Errors
 /// The function returns an `io::Result<()>`:
@@ -305,27 +947,180 @@ fn filter_csv_by_date(input_path: &Path, days_back: i64) -> io::Result<()> {
 ///   output file.
 fn main() -> io::Result<()> {
     let retention_days = 15;
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 3 {
-        eprintln!("Usage: {} <path_to_log_files> <days_back>", args[0]);
-        std::process::exit(1);
-    }
+    let cli = Cli::parse();
 
-    let log_file_path = &args[1];
-    let days_back: i64 = args[2].parse().expect("Invalid number of days");
+    // In `range` mode the retention pass must reuse the same explicit window the user asked
+    // for, not the rolling 15-day default: otherwise a historical `--start`/`--end` older than
+    // 15 days would have every kept record immediately dropped again by `filter_csv_by_date`.
+    let (window, retention_window) = match cli.mode {
+        Mode::Days { days_back } => (
+            DateWindow::from_days_back(days_back),
+            DateWindow::from_days_back(retention_days),
+        ),
+        Mode::Range { start, end } => {
+            let window = DateWindow::from_rfc3339(&start, &end)
+                .unwrap_or_else(|e| panic!("Invalid RFC3339 start/end range: {e}"));
+            (window, window)
+        }
+    };
 
     // Specify the output path directly
     let output_path = Path::new("events.csv");
 
-    let files = filter_files(Path::new(log_file_path), days_back);
-    let mut all_entries = HashSet::new();
+    // `--format copy` prints a standalone snapshot and never touches `events.csv`, unlike the
+    // CSV path, which re-merges via append + `filter_csv_by_date`. So the mtime/size cache must
+    // not be consulted for it (a cached re-run would silently emit only the just-changed files
+    // instead of the full table) or updated by it (that would record files as "processed" that
+    // were never actually merged into `events.csv`, letting a later CSV-format run wrongly skip
+    // them). COPY mode always does a full, uncached scan and leaves the on-disk cache alone.
+    let cache_path = Path::new(CACHE_PATH);
+    let is_copy_mode = matches!(cli.format, OutputFormat::Copy);
+    let cache = if is_copy_mode { HashMap::new() } else { load_cache(cache_path) };
+    let (files, new_cache) = filter_files(Path::new(&cli.path_to_log_files), &window, &cache);
+
+    let handles: Vec<_> = files
+        .into_iter()
+        .map(|source| {
+            let label = source.describe();
+            let window = window;
+            thread::spawn(move || {
+                println!("Processing: {label}");
+                let result =
+                    open_log_source(source).and_then(|reader| process_csv_file(reader, &window));
+                (label, result)
+            })
+        })
+        .collect();
+
+    let mut streams = Vec::with_capacity(handles.len());
+    let mut per_file_stats = Vec::with_capacity(handles.len());
+    let mut aggregate_stats = FileStats::default();
+    for handle in handles {
+        let (label, result) = handle.join().expect("Worker thread panicked");
+        match result {
+            Ok((entries, stats)) => {
+                aggregate_stats.merge(&stats);
+                per_file_stats.push((label, stats));
+                streams.push(entries);
+            }
+            Err(e) => println!("Failed to process file {label}: {e}"),
+        }
+    }
+
+    if cli.summary {
+        print_summary(&per_file_stats, &aggregate_stats);
+    }
+
+    let merged_entries = merge_sorted_streams(streams);
+
+    let result = match cli.format {
+        OutputFormat::Csv => {
+            let written_paths =
+                write_to_csv(merged_entries, output_path, cli.max_size, cli.rotate_daily)?;
+            // Retention/dedup must run over every file this run touched, not just the last one
+            // rotation left active: otherwise `events.csv` (and any intermediate rotated file)
+            // keeps accumulating stale, un-deduped rows across runs once rotation fires.
+            written_paths
+                .iter()
+                .try_for_each(|path| filter_csv_by_date(path, &retention_window))
+        }
+        OutputFormat::Copy => {
+            print_copy_rows(&merged_entries, cli.copy_header);
+            Ok(())
+        }
+    };
+
+    if result.is_ok() && !is_copy_mode {
+        save_cache(cache_path, &new_cache)?;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    for file in files {
-        println!("Processing file: {}", file.path().display());
-        let entries = process_csv_file(&file.path(), days_back)?;
-        all_entries.extend(entries);
+    fn sample_entry(event_description: &str) -> LogEntry {
+        LogEntry {
+            date_time: "2026/01/01 00:00:00".to_string(),
+            source_ip: "10.0.0.1".to_string(),
+            destination_ip: "10.0.0.2".to_string(),
+            event_description: event_description.to_string(),
+            priority: "1".to_string(),
+        }
     }
 
-    let _ = write_to_csv(all_entries, output_path);
-    filter_csv_by_date(output_path, retention_days)
+    #[test]
+    fn merge_sorted_streams_dedups_non_adjacent_duplicates() {
+        let dt = NaiveDate::from_ymd_opt(2026, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let dup = sample_entry("dup");
+        let other = sample_entry("other");
+
+        // All three records share a timestamp, so the heap pops them in worker_index order:
+        // worker 0's `dup`, then worker 1's `other`, then worker 2's `dup` again. The second
+        // `dup` is not adjacent to the first in pop order, which is exactly the case the
+        // previous adjacent-only dedup missed.
+        let streams = vec![
+            vec![(dt, dup.clone())],
+            vec![(dt, other.clone())],
+            vec![(dt, dup.clone())],
+        ];
+
+        let merged = merge_sorted_streams(streams);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged.iter().filter(|entry| **entry == dup).count(), 1);
+        assert_eq!(merged.iter().filter(|entry| **entry == other).count(), 1);
+    }
+
+    #[test]
+    fn filter_files_selects_historical_range_despite_recent_mtime() {
+        let dir = std::env::temp_dir().join(format!(
+            "dashboard_datatable_builder_test_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("fwddmp.log.tmp1"), b"unused").unwrap();
+
+        // The file's real mtime is "now" (it was just created), but the requested window is
+        // entirely in the past. Applying the window's upper bound to mtime (the prior bug)
+        // would exclude this file since mtime > window.end; only the lower bound should apply.
+        let window =
+            DateWindow::from_rfc3339("2020-01-01T00:00:00Z", "2020-01-02T00:00:00Z").unwrap();
+        let (sources, _) = filter_files(&dir, &window, &HashMap::new());
+
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(
+            sources.len(),
+            1,
+            "a file with a recent mtime must still be selected for a historical range"
+        );
+    }
+
+    #[test]
+    fn escape_copy_field_normalizes_and_escapes() {
+        assert_eq!(escape_copy_field(""), "\\N");
+        assert_eq!(escape_copy_field("plain"), "plain");
+        assert_eq!(escape_copy_field("a\tb"), "a\\tb");
+        assert_eq!(escape_copy_field("a\\b"), "a\\\\b");
+        assert_eq!(escape_copy_field("a\nb"), "a\\nb");
+    }
+
+    #[test]
+    fn to_copy_row_does_not_misalign_on_embedded_comma() {
+        let entry = sample_entry("click, then drag");
+        let row = to_copy_row(&entry);
+        assert_eq!(row.split('\t').count(), 5);
+    }
+
+    #[test]
+    fn estimated_record_len_accounts_for_quoting() {
+        assert_eq!(estimated_record_len(["a", "b", "c"]), 6); // "a,b,c\n"
+        assert_eq!(estimated_record_len(["a,b", "c"]), 8); // "\"a,b\",c\n"
+    }
 }